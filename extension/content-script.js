@@ -9,14 +9,31 @@
   let shouldBeBlocked = false;
   let mutationObserverActive = false;
 
-  function isAllowed(hostname, allowedDomains) {
-    if (!allowedDomains || allowedDomains.length === 0) return false;
+  function matchesRule(host, rule) {
+    const domain = (rule || "").trim().toLowerCase();
+    if (!domain) return false;
+    if (domain.startsWith("*.")) return host.endsWith(domain.slice(1));
+    return host === domain || host.endsWith("." + domain);
+  }
+
+  // Mirrors the desktop app's is_local_address
+  function isLocalHost(host) {
+    if (host === "localhost" || host.endsWith(".localhost")) return true;
+    if (host === "[::1]" || /^\[f[cd]/.test(host) || /^\[fe[89ab]/.test(host)) return true;
+    const v4 = host.match(/^(\d+)\.(\d+)\.\d+\.\d+$/);
+    if (!v4) return false;
+    const [a, b] = [Number(v4[1]), Number(v4[2])];
+    return a === 127 || a === 10 || (a === 172 && b >= 16 && b <= 31)
+      || (a === 192 && b === 168) || (a === 169 && b === 254);
+  }
+
+  function isAllowed(hostname, state) {
     const host = hostname.replace(/^www\./, "").toLowerCase();
-    return allowedDomains.some((d) => {
-      const domain = (d || "").trim().toLowerCase();
-      if (!domain) return false;
-      return host === domain || host.endsWith("." + domain);
-    });
+    if (state.blockMode === "denylist") {
+      return !(state.blockedDomains || []).some((d) => matchesRule(host, d));
+    }
+    if (state.allowLocalAddresses && isLocalHost(host)) return true;
+    return (state.allowedDomains || []).some((d) => matchesRule(host, d));
   }
 
   function getOverlay() {
@@ -119,7 +136,9 @@
       return;
     }
 
-    const allowed = isAllowed(hostname, newState.allowedDomains || []);
+    const host = hostname.replace(/^www\./, "").toLowerCase();
+    const tempUnblocked = (newState.tempUnblocked || []).some((d) => matchesRule(host, d));
+    const allowed = tempUnblocked || isAllowed(hostname, newState);
     
     if (allowed) {
       shouldBeBlocked = false;
@@ -8,7 +8,11 @@ chrome.runtime.sendMessage({ type: "GET_STATE" }, (response) => {
     return;
   }
 
-  if (response.lockActive) {
+  if (response.lockActive && response.blockMode === "denylist") {
+    dot.classList.add("active");
+    const count = response.blockedDomains?.length || 0;
+    text.textContent = `Focus active (${count} sites blocked)`;
+  } else if (response.lockActive) {
     dot.classList.add("active");
     const count = response.allowedDomains?.length || 0;
     text.textContent = count > 0 
@@ -17,4 +21,8 @@ chrome.runtime.sendMessage({ type: "GET_STATE" }, (response) => {
   } else {
     text.textContent = "Not in focus mode";
   }
+
+  if (response.lockActive && response.remainingMs > 0) {
+    text.textContent += ` · ${Math.ceil(response.remainingMs / 60000)} min left`;
+  }
 });
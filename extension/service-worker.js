@@ -23,8 +23,10 @@ function connect() {
 
     ws.onmessage = (event) => {
       try {
+        // Rules arrive once per connection; later messages are heartbeats
+        // that only carry what changed
         const newState = JSON.parse(event.data);
-        state = newState;
+        state = newState.lockActive && state.lockActive ? { ...state, ...newState } : newState;
         broadcastState();
       } catch (e) {
         console.error("[Prodblock] Failed to parse message:", e);
@@ -1,5 +1,6 @@
 // Prodblock Frontend - Main JavaScript
 const { invoke } = window.__TAURI__.core;
+const { listen } = window.__TAURI__.event;
 
 // DOM helpers
 const $ = (sel, el = document) => el.querySelector(sel);
@@ -9,6 +10,8 @@ const $$ = (sel, el = document) => el.querySelectorAll(sel);
 let activities = [];
 let suggested = [];
 let selectedActivity = null;
+// Used for activities whose minimum_lock_minutes is 0
+let defaultMinimumLock = 10;
 let lockEndTime = null;
 let lockTimerInterval = null;
 
@@ -26,6 +29,11 @@ function showScreen(id) {
 // UTILITIES
 // ============================================================================
 
+// Commands reject with `{ kind, message }`
+function errorMessage(e) {
+  return e?.message ?? String(e);
+}
+
 function uuid() {
   return "xxxxxxxx-xxxx-4xxx-yxxx-xxxxxxxxxxxx".replace(/[xy]/g, (c) => {
     const r = (Math.random() * 16) | 0;
@@ -48,6 +56,7 @@ function formatDuration(mins) {
 async function loadActivities() {
   try {
     activities = await invoke("get_activities");
+    defaultMinimumLock = (await invoke("get_settings")).default_minimum_lock_minutes;
     return activities;
   } catch (e) {
     console.error("Failed to load activities:", e);
@@ -98,7 +107,7 @@ function renderChoiceScreen() {
 
     const hasApps = a.allowed_apps && a.allowed_apps.length > 0;
     const hasDomains = a.allowed_domains && a.allowed_domains.length > 0;
-    let meta = `${a.minimum_lock_minutes || 10} min`;
+    let meta = `${a.minimum_lock_minutes || defaultMinimumLock} min`;
     if (!hasApps && !hasDomains) {
       meta += " • Full focus (no apps/sites)";
     } else {
@@ -146,7 +155,7 @@ function showConfirmation() {
   
   const hasApps = selectedActivity.allowed_apps?.length > 0;
   const hasDomains = selectedActivity.allowed_domains?.length > 0;
-  let details = `${selectedActivity.minimum_lock_minutes || 10} minutes minimum`;
+  let details = `${selectedActivity.minimum_lock_minutes || defaultMinimumLock} minutes minimum`;
   
   if (!hasApps && !hasDomains) {
     details += " • All distractions blocked";
@@ -163,23 +172,43 @@ function showConfirmation() {
 async function startLock() {
   if (!selectedActivity) return;
 
-  const lockMinutes = selectedActivity.minimum_lock_minutes || 10;
+  const lockMinutes = selectedActivity.minimum_lock_minutes || defaultMinimumLock;
+  let enforcement;
   
   try {
-    await invoke("start_lock", {
-      activityId: selectedActivity.id,
-      whitelist: selectedActivity.allowed_apps || [],
-      allowedDomains: selectedActivity.allowed_domains || [],
-      minimumLockMinutes: lockMinutes,
-    });
+    enforcement = await invoke("start_lock_by_id", { activityId: selectedActivity.id });
   } catch (e) {
     console.error("Failed to start lock:", e);
-    alert("Failed to start focus session: " + e);
+    alert("Failed to start focus session: " + errorMessage(e));
     return;
   }
 
+  showLockScreen(selectedActivity, lockMinutes);
+  showEnforcementWarning(enforcement);
+}
+
+// Say plainly what this OS can't block, rather than let the lock look airtight
+let currentEnforcement = null;
+
+function showEnforcementWarning(enforcement) {
+  currentEnforcement = enforcement;
+  const missing = [];
+  if (enforcement && !enforcement.apps) missing.push("app blocking");
+  if (enforcement && !enforcement.websites) missing.push("website blocking");
+  $("#lock-enforcement-warning").textContent = missing.length
+    ? `⚠️ ${missing.join(" and ")} isn't supported on this system.`
+    : "";
+}
+
+function showLockScreen(activity, lockMinutes) {
   lockEndTime = Date.now() + lockMinutes * 60 * 1000;
-  $("#lock-activity-name").textContent = selectedActivity.name;
+  emergencyReason = null;
+  $("#lock-enforcement-warning").textContent = "";
+  $("#lock-health-warning").textContent = "";
+  $("#lock-emergency").textContent = "Emergency exit";
+  $("#lock-activity-name").textContent = activity.name;
+  $("#lock-activity-goal").textContent = activity.goal || "";
+  $("#lock-activity-description").textContent = activity.description || "";
   showScreen("lock");
   startLockTimer();
 }
@@ -190,16 +219,37 @@ function startLockTimer() {
 
   if (lockTimerInterval) clearInterval(lockTimerInterval);
 
-  const updateTimer = () => {
-    const remaining = Math.max(0, lockEndTime - Date.now());
+  const updateTimer = async () => {
+    let remaining = Math.max(0, lockEndTime - Date.now());
+    let canFinish = remaining <= 0;
+    let targetRemaining = 0;
+    try {
+      const status = await invoke("get_lock_status");
+      remaining = status.remaining_ms;
+      canFinish = status.can_finish;
+      targetRemaining = status.target_remaining_ms;
+      lockEndTime = Date.now() + remaining;
+      // Past the minimum, keep counting down toward the target
+      if (canFinish && targetRemaining > 0) remaining = targetRemaining;
+      $(".timer-label").textContent = status.idle
+        ? "Paused while idle"
+        : canFinish && targetRemaining > 0
+          ? "Minimum reached, keep going toward your target"
+          : "Time remaining";
+      const { apps_blocked: apps, sites_blocked: sites } = status;
+      $("#lock-stats").textContent = apps || sites
+        ? `Blocked ${apps} app${apps === 1 ? "" : "s"} and ${sites} site${sites === 1 ? "" : "s"} this session`
+        : "";
+    } catch (e) {
+      console.error("Failed to get lock status:", e);
+    }
     const mins = Math.floor(remaining / 60000);
     const secs = Math.floor((remaining % 60000) / 1000);
     timerEl.textContent = `${mins}:${secs.toString().padStart(2, "0")}`;
 
-    const canFinish = remaining <= 0;
     doneBtn.disabled = !canFinish;
 
-    if (canFinish) {
+    if (canFinish && targetRemaining <= 0) {
       clearInterval(lockTimerInterval);
       lockTimerInterval = null;
     }
@@ -209,6 +259,50 @@ function startLockTimer() {
   lockTimerInterval = setInterval(updateTimer, 500);
 }
 
+// The emergency exit asks for a reason, then must be held down; the
+// backend times the hold and ends the lock itself
+let emergencyReason = null;
+
+function setupEmergencyExit() {
+  const btn = $("#lock-emergency");
+  if (!btn) return;
+
+  btn.addEventListener("click", () => {
+    if (emergencyReason) return;
+    const reason = prompt("Why do you need to end this lock early?");
+    if (!reason || !reason.trim()) return;
+    emergencyReason = reason.trim();
+    btn.textContent = "Hold to exit";
+  });
+  btn.addEventListener("pointerdown", async () => {
+    if (!emergencyReason) return;
+    try {
+      await invoke("begin_emergency_exit", { reason: emergencyReason });
+    } catch (e) {
+      console.error("Failed to start emergency exit:", e);
+    }
+  });
+  const release = () => invoke("cancel_emergency_exit").catch(() => {});
+  btn.addEventListener("pointerup", release);
+  btn.addEventListener("pointerleave", release);
+
+  listen("emergency-exit-progress", (event) => {
+    const { held_ms: held, required_ms: required } = event.payload;
+    btn.textContent = held > 0
+      ? `Keep holding… ${Math.ceil((required - held) / 1000)}s`
+      : "Hold to exit";
+  });
+  listen("lock-ended", async () => {
+    emergencyReason = null;
+    btn.textContent = "Emergency exit";
+    if (lockTimerInterval) {
+      clearInterval(lockTimerInterval);
+      lockTimerInterval = null;
+    }
+    if ($("#lock")?.classList.contains("active")) await goToChoice();
+  });
+}
+
 async function endLock() {
   try {
     await invoke("end_lock");
@@ -244,7 +338,7 @@ function renderConfigList() {
     li.innerHTML = `
       <div class="activity-info">
         <span class="activity-title">${escapeHtml(a.name)}</span>
-        <span class="activity-time">${a.typical_time || "Any time"} • ${a.minimum_lock_minutes || 10} min</span>
+        <span class="activity-time">${a.typical_time || "Any time"} • ${a.minimum_lock_minutes || defaultMinimumLock} min</span>
       </div>
       <div class="activity-actions">
         <button data-id="${a.id}" class="edit">Edit</button>
@@ -293,6 +387,9 @@ function openActivityForm(id) {
   $("#form-name").value = a?.name || "";
   $("#form-time").value = a?.typical_time || "";
   $("#form-min-lock").value = a?.minimum_lock_minutes || 25;
+  $("#form-goal").value = a?.goal || "";
+  $("#form-description").value = a?.description || "";
+  $("#form-tags").value = (a?.tags || []).join(", ");
   $("#form-apps").value = (a?.allowed_apps || []).join("\n");
   $("#form-domains").value = (a?.allowed_domains || []).join("\n");
 
@@ -306,6 +403,12 @@ async function saveActivity(ev) {
   const name = $("#form-name").value.trim();
   const typical_time = $("#form-time").value.trim() || "00:00";
   const minimum_lock_minutes = parseInt($("#form-min-lock").value, 10) || 25;
+  const goal = $("#form-goal").value.trim();
+  const description = $("#form-description").value.trim();
+  const tags = $("#form-tags").value
+    .split(",")
+    .map((s) => s.trim())
+    .filter(Boolean);
   const allowed_apps = $("#form-apps").value
     .split("\n")
     .map((s) => s.trim())
@@ -320,7 +423,10 @@ async function saveActivity(ev) {
     return;
   }
 
+  // Keep fields the form doesn't edit (schedule, etc.)
+  const existing = activities.find((x) => x.id === id) || {};
   const activity = {
+    ...existing,
     id,
     name,
     typical_time,
@@ -328,6 +434,9 @@ async function saveActivity(ev) {
     minimum_lock_minutes,
     allowed_apps,
     allowed_domains,
+    description,
+    goal,
+    tags,
   };
 
   const idx = activities.findIndex((x) => x.id === id);
@@ -338,10 +447,10 @@ async function saveActivity(ev) {
   }
 
   try {
-    await invoke("save_activities", { activities });
+    activities = await invoke("save_activities", { activities });
   } catch (e) {
     console.error("Failed to save activities:", e);
-    alert("Failed to save: " + e);
+    alert("Failed to save: " + errorMessage(e));
     return;
   }
 
@@ -384,6 +493,7 @@ async function init() {
 
   // Lock screen
   $("#lock-done")?.addEventListener("click", endLock);
+  setupEmergencyExit();
   
   // Dev-only escape hatch
   const testingBtn = $("#lock-exit-testing");
@@ -424,6 +534,52 @@ async function init() {
     showScreen("config");
   });
 
+  // Locks armed by an activity's schedule
+  listen("scheduled-lock-started", async (event) => {
+    await loadActivities();
+    const activity = activities.find((a) => a.id === event.payload);
+    if (!activity) return;
+    selectedActivity = activity;
+    showLockScreen(activity, activity.minimum_lock_minutes || defaultMinimumLock);
+  });
+
+  // A prodblock:// link clicked while prodblock is already running
+  listen("activity-imported", async () => {
+    await loadActivities();
+    if ($("#config")?.classList.contains("active")) renderConfigList();
+  });
+
+  // Each step of a routine arms its own lock
+  listen("routine-step-changed", async (event) => {
+    await loadActivities();
+    const activity = activities.find((a) => a.id === event.payload.activity_id);
+    if (!activity) return;
+    selectedActivity = activity;
+    showLockScreen(activity, event.payload.minutes);
+  });
+
+  // Pushed whenever the proxy, watcher or extension server starts or stops
+  listen("enforcement-health-changed", (event) => {
+    if (!$("#lock")?.classList.contains("active")) return;
+    const health = event.payload;
+    const down = [];
+    if (currentEnforcement?.websites && !health.proxy_running) down.push("website blocking");
+    if (currentEnforcement?.apps && !health.watcher_running) down.push("app blocking");
+    $("#lock-health-warning").textContent = down.length
+      ? `⚠️ ${down.join(" and ")} stopped running.`
+      : "";
+  });
+
+  // A lock picked back up after a restart goes straight to the lock screen
+  const resumed = await invoke("get_active_lock_config").catch(() => null);
+  if (resumed) {
+    const status = await invoke("get_lock_status");
+    selectedActivity = activities.find((a) => a.id === resumed.activity_id)
+      || { id: resumed.activity_id, name: resumed.activity_id };
+    showLockScreen(selectedActivity, status.remaining_ms / 60000);
+    return;
+  }
+
   // Start on choice screen
   await goToChoice();
 }